@@ -5,7 +5,7 @@
 use super::call::FnCallArgs;
 use super::callable_function::CallableFunction;
 use super::native::{SendSync, Shared};
-use crate::types::dynamic::{DynamicWriteLock, Variant};
+use crate::types::dynamic::{DynamicReadLock, DynamicWriteLock, Variant};
 use crate::{reify, Dynamic, NativeCallContext, RhaiResultOf};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -29,7 +29,14 @@ use std::{any::TypeId, mem};
 ///
 /// These types are not actually used anywhere.
 pub struct Mut<T>(T);
-//pub struct Ref<T>(T);
+/// Dereferencing marker for the leading parameter taken by shared reference (`&T`).
+///
+/// Like [`Mut`], this type deliberately does **not** implement `Clone`, so it can never
+/// unify with a free `Variant + Clone` type parameter. That is what lets the `Fn(T, ...)`
+/// and `Fn(&T, ...)` impls for the same arity co-exist: without this, the two trait
+/// implementations would be indistinguishable to the coherence checker because `&T: Any`
+/// and `T: Any` cannot otherwise be told apart.
+pub struct Ref<T>(T);
 
 /// Dereference into [`DynamicWriteLock`]
 #[inline(always)]
@@ -39,6 +46,14 @@ pub fn by_ref<T: Variant + Clone>(data: &mut Dynamic) -> DynamicWriteLock<T> {
     data.write_lock::<T>().expect("checked")
 }
 
+/// Dereference into [`DynamicReadLock`], producing a read-only borrow of the underlying data.
+#[inline(always)]
+#[must_use]
+pub fn by_shared_ref<T: Variant + Clone>(data: &mut Dynamic) -> DynamicReadLock<T> {
+    // Directly cast the &mut Dynamic into DynamicReadLock to access the underlying data.
+    data.read_lock::<T>().expect("checked")
+}
+
 /// Dereference into value.
 #[inline(always)]
 #[must_use]
@@ -54,6 +69,11 @@ pub fn by_value<T: Variant + Clone>(data: &mut Dynamic) -> T {
         // If T is `String`, data must be `ImmutableString`, so map directly to it
         return reify!(mem::take(data).into_string().expect("`ImmutableString`") => T);
     }
+    #[cfg(not(feature = "no_index"))]
+    if TypeId::of::<T>() == TypeId::of::<crate::Blob>() {
+        // If T is `Blob` (`Vec<u8>`), data must be `Blob`, so take directly out of it
+        return reify!(mem::take(data).into_blob().expect("`Blob`") => T);
+    }
 
     // We consume the argument and then replace it with () - the argument is not supposed to be used again.
     // This way, we avoid having to clone the argument again, because it is already a clone when passed here.
@@ -64,7 +84,8 @@ pub fn by_value<T: Variant + Clone>(data: &mut Dynamic) -> T {
 ///
 /// # Type Parameters
 ///
-/// * `ARGS` - a tuple containing parameter types, with `&mut T` represented by `Mut<T>`.
+/// * `ARGS` - a tuple containing parameter types, with `&mut T` represented by `Mut<T>`
+///   and a leading `&T` represented by `Ref<T>`.
 /// * `RET` - return type of the function; if the function returns `Result`, it is the unwrapped inner value type.
 pub trait RegisterNativeFunction<ARGS, RET, RESULT> {
     /// Convert this function into a [`CallableFunction`].
@@ -233,18 +254,28 @@ macro_rules! def_register {
         //def_register!(imp_pop $($par => $mark => $param),*);
     };
     ($p0:ident $(, $p:ident)*) => {
-        def_register!(imp Pure   : $p0 => $p0      => $p0      => $p0      => let $p0     => by_value $(, $p => $p => $p => $p => let $p => by_value)*);
-        def_register!(imp Method : $p0 => &mut $p0 => Mut<$p0> => &mut $p0 => let mut $p0 => by_ref   $(, $p => $p => $p => $p => let $p => by_value)*);
+        def_register!(imp Pure   : $p0 => $p0      => $p0      => $p0      => let $p0     => by_value      $(, $p => $p => $p => $p => let $p => by_value)*);
+        def_register!(imp Method : $p0 => &mut $p0 => Mut<$p0> => &mut $p0 => let mut $p0 => by_ref        $(, $p => $p => $p => $p => let $p => by_value)*);
+        def_register!(imp Method : $p0 => &$p0     => Ref<$p0> => &$p0     => let $p0     => by_shared_ref $(, $p => $p => $p => $p => let $p => by_value)*);
         //                ^ CallableFunction constructor
         //                                                             ^ first parameter passed through
         //                                                                                                     ^ others passed by value (by_value)
 
-        // Currently does not support first argument which is a reference, as there will be
-        // conflicting implementations since &T: Any and T: Any cannot be distinguished
-        //def_register!(imp $p0 => Ref<$p0> => &$p0     => by_ref   $(, $p => $p => $p => by_value)*);
-
         def_register!($($p),*);
     };
 }
 
+// TODO(mesatee/rhai#chunk0-2): register genuinely variadic functions shaped like
+// `Fn(NativeCallContext, &mut FnCallArgs) -> RhaiResultOf<RET>`, taking the whole argument
+// list instead of a fixed arity. An `ARGS` marker alone isn't enough: `param_types()` has
+// no way to tell call resolution "any arity matches", so this needs dispatch-level support
+// for an arity wildcard (outside this module) before a `RegisterNativeFunction` impl for it
+// can do anything useful.
+
+// TODO(mesatee/rhai#chunk0-3): register native functions backed by async Rust, returning
+// `Pin<Box<dyn Future<Output = RhaiResultOf<RET>>>>`. This needs an executor hook reachable
+// from `NativeCallContext` (and an `Engine`-level block-on registration to back it) so the
+// generated `CallableFunction` has something to drive the future with; neither exists in
+// this module.
+
 def_register!(A, B, C, D, E, F, G, H, J, K, L, M, N, P, Q, R, S, T, U, V);